@@ -26,7 +26,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().skip(1).collect();
     let exe_path = env::current_exe()?;
     let exe_dir = exe_path.parent().unwrap();
-    
+
     // 2. THE DAEMON PROBE
     // We attempt to find the Project Root to locate the Pulse file.
     if let Some(project_root) = find_root() {
@@ -0,0 +1,153 @@
+// ---------------------
+// Path: rust/src/cdc.rs
+// ---------------------
+// Content-defined chunking for incremental hashing: a Gear-hash rolling
+// checksum splits a file into content-aligned chunks, so a small edit
+// only invalidates the chunks around it instead of the whole file.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyIOError;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+
+// Target average chunk size of ~8 KiB: a boundary fires when the low 13
+// bits of the rolling hash are all zero.
+const BOUNDARY_BITS: u32 = 13;
+const BOUNDARY_MASK: u64 = (1u64 << BOUNDARY_BITS) - 1;
+const MIN_CHUNK_SIZE: usize = 2 * 1024;
+const MAX_CHUNK_SIZE: usize = 64 * 1024;
+
+// A fixed table of random u64s, one per byte value, used to mix each byte
+// into the rolling hash (the "Gear" construction).
+const GEAR_TABLE: [u64; 256] = build_gear_table();
+
+const fn build_gear_table() -> [u64; 256] {
+    // A small xorshift-style PRNG, seeded with a fixed constant, unrolled
+    // at compile time so the table is deterministic across builds.
+    let mut table = [0u64; 256];
+    let mut seed: u64 = 0x9E3779B97F4A7C15;
+    let mut i = 0;
+    while i < 256 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        table[i] = seed;
+        i += 1;
+    }
+    table
+}
+
+// =================================================================================
+// == THE GNOSTIC VESSELS (STRUCTS)                                               ==
+// =================================================================================
+
+#[pyclass]
+#[derive(Clone)]
+pub struct Chunk {
+    #[pyo3(get)]
+    pub offset: u64,
+    #[pyo3(get)]
+    pub len: u64,
+    #[pyo3(get)]
+    pub sha256: String,
+}
+
+#[pymethods]
+impl Chunk {
+    fn __repr__(&self) -> String {
+        format!("<Chunk offset={} len={} sha256={}>", self.offset, self.len, self.sha256)
+    }
+}
+
+#[pyclass]
+pub struct ChunkStore {
+    seen: HashSet<String>,
+}
+
+#[pymethods]
+impl ChunkStore {
+    #[new]
+    fn new() -> Self {
+        ChunkStore { seen: HashSet::new() }
+    }
+
+    /// Given the chunks of a (possibly changed) file, return only those
+    /// whose hash hasn't been recorded yet, and remember them as seen.
+    /// Callers merge the known chunks back in, so only novel content needs
+    /// to be re-read and stored.
+    fn merge(&mut self, chunks: Vec<Chunk>) -> Vec<Chunk> {
+        let mut novel = Vec::new();
+        for chunk in chunks {
+            if self.seen.insert(chunk.sha256.clone()) {
+                novel.push(chunk);
+            }
+        }
+        novel
+    }
+
+    fn contains(&self, sha256: &str) -> bool {
+        self.seen.contains(sha256)
+    }
+
+    fn len(&self) -> usize {
+        self.seen.len()
+    }
+}
+
+// =================================================================================
+// == THE IRON ARTISANS (FUNCTIONS)                                               ==
+// =================================================================================
+
+fn hash_chunk(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+/// Split `data` into content-defined chunks using a Gear rolling hash: a
+/// boundary fires once `BOUNDARY_MASK` bits of the running hash are zero,
+/// bounded below by `MIN_CHUNK_SIZE` (to avoid degenerate tiny chunks) and
+/// above by `MAX_CHUNK_SIZE` (to bound worst-case variance).
+fn split_chunks(data: &[u8]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0usize;
+    let mut h: u64 = 0;
+
+    for i in 0..data.len() {
+        let chunk_len = i - start + 1;
+        h = (h << 1).wrapping_add(GEAR_TABLE[data[i] as usize]);
+
+        let at_boundary = chunk_len >= MIN_CHUNK_SIZE && (h & BOUNDARY_MASK) == 0;
+        let at_max = chunk_len >= MAX_CHUNK_SIZE;
+
+        if at_boundary || at_max {
+            ranges.push((start, i + 1));
+            start = i + 1;
+            h = 0;
+        }
+    }
+
+    if start < data.len() {
+        ranges.push((start, data.len()));
+    }
+
+    ranges
+}
+
+#[pyfunction]
+pub fn chunk_file(path: String) -> PyResult<Vec<Chunk>> {
+    let mut file = File::open(&path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+    Ok(split_chunks(&data)
+        .into_iter()
+        .map(|(start, end)| Chunk {
+            offset: start as u64,
+            len: (end - start) as u64,
+            sha256: hash_chunk(&data[start..end]),
+        })
+        .collect())
+}
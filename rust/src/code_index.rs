@@ -0,0 +1,343 @@
+// ---------------------
+// Path: rust/src/code_index.rs
+// ---------------------
+// A semantic index over a scanned tree: chunk every file into named units
+// (functions, methods, classes, impls) via tree-sitter, fall back to a
+// sliding window for files a query finds nothing in, and let Python attach
+// embedding vectors for nearest-neighbour search.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::fs;
+use std::path::Path;
+use tree_sitter::{Parser, Query, QueryCursor};
+
+use crate::collect_source_files;
+
+// =================================================================================
+// == THE GNOSTIC VESSELS (STRUCTS)                                               ==
+// =================================================================================
+
+#[pyclass]
+#[derive(Clone)]
+pub struct CodeChunk {
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub symbol_name: Option<String>,
+}
+
+#[pymethods]
+impl CodeChunk {
+    fn __repr__(&self) -> String {
+        format!(
+            "<CodeChunk path='{}' kind='{}' symbol={:?}>",
+            self.path, self.kind, self.symbol_name
+        )
+    }
+}
+
+#[pyclass]
+pub struct CodeIndex {
+    entries: Vec<(CodeChunk, Vec<f32>)>,
+    // Dimension of the first embedding inserted; every later insert and
+    // query must match it, or the dot product would silently compare
+    // vectors from two different embedding spaces.
+    dim: Option<usize>,
+}
+
+#[pymethods]
+impl CodeIndex {
+    #[new]
+    fn new() -> Self {
+        CodeIndex { entries: Vec::new(), dim: None }
+    }
+
+    /// Attach an embedding vector to each chunk, in order, normalizing it
+    /// once so that search() can score with a plain dot product.
+    fn embed_chunks(&mut self, chunks: Vec<CodeChunk>, vectors: Vec<Vec<f32>>) -> PyResult<()> {
+        if chunks.len() != vectors.len() {
+            return Err(PyValueError::new_err(
+                "chunks and vectors must have the same length",
+            ));
+        }
+
+        let expected_dim = self.dim.or_else(|| vectors.first().map(Vec::len));
+        if let Some(dim) = expected_dim {
+            if let Some(bad) = vectors.iter().position(|v| v.len() != dim) {
+                return Err(PyValueError::new_err(format!(
+                    "embedding at index {} has dimension {} but this index expects {}",
+                    bad,
+                    vectors[bad].len(),
+                    dim
+                )));
+            }
+        }
+        // A NaN/Inf component would survive normalize() (its norm isn't
+        // exactly 0.0) and poison every later search()'s sort.
+        if let Some(bad) = vectors.iter().position(|v| v.iter().any(|x| !x.is_finite())) {
+            return Err(PyValueError::new_err(format!(
+                "embedding at index {} contains a non-finite component",
+                bad
+            )));
+        }
+        self.dim = expected_dim;
+
+        for (chunk, vector) in chunks.into_iter().zip(vectors.into_iter()) {
+            self.entries.push((chunk, normalize(&vector)));
+        }
+        Ok(())
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Brute-force cosine search: vectors are pre-normalized at insert time,
+    /// so scoring is just a dot product. Fine for a first cut; a repo-sized
+    /// index is a few hundred thousand floats at most.
+    fn search(&self, query_vector: Vec<f32>, k: usize) -> PyResult<Vec<(CodeChunk, f32)>> {
+        if let Some(dim) = self.dim {
+            if query_vector.len() != dim {
+                return Err(PyValueError::new_err(format!(
+                    "query vector has dimension {} but this index expects {}",
+                    query_vector.len(),
+                    dim
+                )));
+            }
+        }
+
+        let query = normalize(&query_vector);
+        let mut scored: Vec<(f32, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, (_, v))| (dot(&query, v), i))
+            .collect();
+
+        let k = k.min(scored.len());
+        if k > 0 {
+            // total_cmp instead of partial_cmp: a non-finite score (from a
+            // stray query component, say) sorts to one end instead of
+            // panicking, on top of embed_chunks already rejecting
+            // non-finite stored embeddings.
+            scored.select_nth_unstable_by(k - 1, |a, b| b.0.total_cmp(&a.0));
+        }
+        scored.truncate(k);
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        Ok(scored
+            .into_iter()
+            .map(|(score, i)| (self.entries[i].0.clone(), score))
+            .collect())
+    }
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+// =================================================================================
+// == THE IRON ARTISANS (FUNCTIONS)                                               ==
+// =================================================================================
+
+const SLIDING_WINDOW_LINES: usize = 40;
+const SLIDING_WINDOW_OVERLAP: usize = 10;
+
+/// Per-language capture query for the top-level semantic units we want to
+/// index as their own chunks. A file in which the query matches nothing (or
+/// a language we don't recognize) falls back to the sliding-window chunker;
+/// in a file with at least one match, code outside the units is not indexed.
+fn capture_query_for(language: &str) -> Option<(tree_sitter::Language, &'static str)> {
+    match language {
+        "python" => Some((
+            tree_sitter_python::language(),
+            "(function_definition name: (identifier) @name) @unit
+             (class_definition name: (identifier) @name) @unit",
+        )),
+        "javascript" => Some((
+            tree_sitter_javascript::language(),
+            "(function_declaration name: (identifier) @name) @unit
+             (method_definition name: (property_identifier) @name) @unit
+             (class_declaration name: (identifier) @name) @unit",
+        )),
+        "typescript" | "tsx" => Some((
+            tree_sitter_typescript::language_tsx(),
+            "(function_declaration name: (identifier) @name) @unit
+             (method_definition name: (property_identifier) @name) @unit
+             (class_declaration name: (type_identifier) @name) @unit",
+        )),
+        "go" => Some((
+            tree_sitter_go::language(),
+            "(function_declaration name: (identifier) @name) @unit
+             (method_declaration name: (field_identifier) @name) @unit",
+        )),
+        "rust" => Some((
+            tree_sitter_rust::language(),
+            "(function_item name: (identifier) @name) @unit
+             (impl_item type: (type_identifier) @name) @unit",
+        )),
+        _ => None,
+    }
+}
+
+fn language_for_path(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "py" => Some("python"),
+        "js" | "jsx" | "mjs" => Some("javascript"),
+        "ts" => Some("typescript"),
+        "tsx" => Some("tsx"),
+        "go" => Some("go"),
+        "rs" => Some("rust"),
+        _ => None,
+    }
+}
+
+/// Byte offset where each line starts, found by scanning for literal `\n`
+/// bytes rather than trusting `str::lines()` + a fixed `+1` stride: a CRLF
+/// file has a `\r` before every `\n`, which would otherwise drift every
+/// offset after the first line.
+fn line_start_offsets(content: &str) -> Vec<usize> {
+    let bytes = content.as_bytes();
+    let mut offsets = vec![0usize];
+    for (i, &b) in bytes.iter().enumerate() {
+        if b == b'\n' {
+            offsets.push(i + 1);
+        }
+    }
+    // Match str::lines(): a trailing "\n" doesn't introduce one more
+    // (empty) line, so don't count the offset it would start.
+    if offsets.len() > 1 && offsets.last() == Some(&bytes.len()) {
+        offsets.pop();
+    }
+    offsets
+}
+
+/// Split `content` into sliding-window chunks when no capture query applies.
+/// Windows overlap, so a unit of at most `SLIDING_WINDOW_OVERLAP` lines that
+/// straddles a boundary still appears whole in at least one chunk.
+fn sliding_window_chunks(path: &str, content: &str) -> Vec<CodeChunk> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    let mut line_offsets = line_start_offsets(content);
+    line_offsets.push(content.len());
+    let num_lines = line_offsets.len() - 1;
+
+    let mut chunks = Vec::new();
+    let stride = SLIDING_WINDOW_LINES.saturating_sub(SLIDING_WINDOW_OVERLAP).max(1);
+    let mut start_line = 0usize;
+
+    loop {
+        let end_line = (start_line + SLIDING_WINDOW_LINES).min(num_lines);
+        let start_byte = line_offsets[start_line];
+        let end_byte = line_offsets[end_line].min(content.len());
+        let text = content[start_byte..end_byte].to_string();
+
+        chunks.push(CodeChunk {
+            path: path.to_string(),
+            start_byte,
+            end_byte,
+            kind: "window".to_string(),
+            text,
+            symbol_name: None,
+        });
+
+        if end_line >= num_lines {
+            break;
+        }
+        start_line += stride;
+    }
+
+    chunks
+}
+
+fn query_chunks(path: &str, content: &str, language: &str) -> Option<Vec<CodeChunk>> {
+    let (lang, query_str) = capture_query_for(language)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(lang).ok()?;
+    let tree = parser.parse(content, None)?;
+    let query = Query::new(lang, query_str).ok()?;
+    let mut cursor = QueryCursor::new();
+    let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+    let name_idx = query.capture_names().iter().position(|n| n == "name");
+    let unit_idx = query.capture_names().iter().position(|n| n == "unit")?;
+
+    let mut chunks = Vec::new();
+    for m in matches {
+        let unit_capture = m.captures.iter().find(|c| c.index as usize == unit_idx)?;
+        let node = unit_capture.node;
+        let range = node.byte_range();
+        let symbol_name = name_idx.and_then(|idx| {
+            m.captures
+                .iter()
+                .find(|c| c.index as usize == idx)
+                .map(|c| content[c.node.byte_range()].to_string())
+        });
+
+        chunks.push(CodeChunk {
+            path: path.to_string(),
+            start_byte: range.start,
+            end_byte: range.end,
+            kind: node.kind().to_string(),
+            text: content[range].to_string(),
+            symbol_name,
+        });
+    }
+
+    if chunks.is_empty() {
+        None
+    } else {
+        Some(chunks)
+    }
+}
+
+/// Chunk a single file: prefer the per-language capture query, and fall
+/// back to the sliding window when the query matches nothing in the file
+/// (or the language is unrecognized) so no readable file is left out.
+fn chunk_file(path: &Path) -> Vec<CodeChunk> {
+    let path_str = path.to_string_lossy().replace('\\', "/");
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    if let Some(language) = language_for_path(path) {
+        if let Some(chunks) = query_chunks(&path_str, &content, language) {
+            return chunks;
+        }
+    }
+
+    sliding_window_chunks(&path_str, &content)
+}
+
+#[pyfunction]
+pub fn index_directory(py: Python, root: String) -> PyResult<Vec<CodeChunk>> {
+    py.allow_threads(move || {
+        let mut chunks = Vec::new();
+        for path in collect_source_files(&root) {
+            chunks.extend(chunk_file(&path));
+        }
+        Ok(chunks)
+    })
+}
@@ -6,22 +6,26 @@ use pyo3::exceptions::{PyIOError, PyValueError};
 use ignore::WalkBuilder;
 use sha2::{Sha256, Digest};
 use std::fs::{self, File};
-use std::io::Read;
 use memmap2::MmapOptions;
 use std::time::SystemTime;
-use std::thread;
 use std::collections::HashMap;
     
 // The Divine Summons of the Syntax Trees
 // Ensure your Cargo.toml has tree-sitter dependencies!
 use tree_sitter::{Parser, Query, QueryCursor};
-    
+
+mod cdc;
+mod code_index;
+mod scan_cache;
+mod secrets;
+mod wire;
+
 // =================================================================================
 // == THE GNOSTIC VESSELS (STRUCTS)                                               ==
 // =================================================================================
     
 #[pyclass]
-struct FileRecord {
+pub(crate) struct FileRecord {
     #[pyo3(get)]
     path: String,
     #[pyo3(get)]
@@ -30,15 +34,20 @@ struct FileRecord {
     is_binary: bool,
     #[pyo3(get)]
     mtime: f64,
+    // Only populated when scan_directory is given a ScanCache: the content
+    // hash, either reused from the cache or freshly computed.
+    #[pyo3(get)]
+    sha256: Option<String>,
 }
-    
+
 #[pymethods]
 impl FileRecord {
     #[new]
-    fn new(path: String, size: u64, is_binary: bool, mtime: f64) -> Self {
-        FileRecord { path, size, is_binary, mtime }
+    #[pyo3(signature = (path, size, is_binary, mtime, sha256=None))]
+    pub(crate) fn new(path: String, size: u64, is_binary: bool, mtime: f64, sha256: Option<String>) -> Self {
+        FileRecord { path, size, is_binary, mtime, sha256 }
     }
-        
+
     fn __repr__(&self) -> String {
         format!("<FileRecord path='{}' size={} binary={}>", self.path, self.size, self.is_binary)
     }
@@ -48,12 +57,12 @@ impl FileRecord {
 // == THE IRON ARTISANS (FUNCTIONS)                                               ==
 // =================================================================================
     
-fn is_binary_buffer(buffer: &[u8]) -> bool {
+pub(crate) fn is_binary_buffer(buffer: &[u8]) -> bool {
     // A heuristic: if we find a null byte in the first 8KB, it's likely binary.
     buffer.iter().take(8192).any(|&b| b == 0)
 }
     
-fn system_time_to_float(t: SystemTime) -> f64 {
+pub(crate) fn system_time_to_float(t: SystemTime) -> f64 {
     match t.duration_since(SystemTime::UNIX_EPOCH) {
         Ok(d) => d.as_secs_f64(),
         Err(_) => 0.0,
@@ -78,7 +87,7 @@ fn hash_file(path: String) -> PyResult<String> {
 }
     
 #[pyfunction]
-fn calculate_entropy(data: &[u8]) -> f64 {
+pub(crate) fn calculate_entropy(data: &[u8]) -> f64 {
     if data.is_empty() { return 0.0; }
     let mut counts = [0usize; 256];
     for &byte in data { counts[byte as usize] += 1; }
@@ -93,51 +102,72 @@ fn calculate_entropy(data: &[u8]) -> f64 {
     entropy
 }
     
+/// Walk `root` the same way `scan_directory` does, ignoring hidden files
+/// and respecting .gitignore, and return the plain file paths. Shared by
+/// subsystems (the code index, secret scanning) that need to fan out over
+/// a tree without paying for the full `FileRecord` metadata.
+pub(crate) fn collect_source_files(root: &str) -> Vec<std::path::PathBuf> {
+    let walker = WalkBuilder::new(root).hidden(true).git_ignore(true).build();
+    walker
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
+}
+
 /// [THE FIX] Renamed to scan_directory to match Python expectation
+///
+/// When `cache` is given, each file is first checked against its recorded
+/// `{size, mtime}`: on a match we reuse the cached hash and binary flag
+/// instead of re-reading the file at all; on a miss we hash it fresh (like
+/// `hash_file`) and update the cache entry in place.
 #[pyfunction]
-#[pyo3(signature = (root, hidden=false))]
-fn scan_directory(py: Python, root: String, hidden: bool) -> PyResult<Vec<FileRecord>> {
+#[pyo3(signature = (root, hidden=false, cache=None))]
+fn scan_directory(
+    py: Python,
+    root: String,
+    hidden: bool,
+    cache: Option<Py<scan_cache::ScanCache>>,
+) -> PyResult<Vec<FileRecord>> {
+    // Snapshot the cache's current entries before dropping the GIL; once
+    // the walk is done, the fresh state replaces them (paths not seen in
+    // this walk are dropped).
+    let snapshot = match &cache {
+        Some(c) => c.borrow(py).entries.clone(),
+        None => HashMap::new(),
+    };
+    let use_cache = cache.is_some();
+
     // We release the GIL to allow Python to breathe while we work.
-    py.allow_threads(move || {
-        let mut results = Vec::new();
-        // Auto-detect thread count for maximum parallelism
-        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
-    
-        let walker = WalkBuilder::new(&root)
-            .hidden(!hidden) 
-            .git_ignore(true) 
-            .threads(threads) 
-            .build();
-    
-        for result in walker {
-            match result {
-                Ok(entry) => {
-                    if !entry.path().is_file() { continue; }
-                    let path = entry.path();
-                    let path_str = path.to_string_lossy().replace("\\", "/");
-    
-                    if let Ok(metadata) = entry.metadata() {
-                        let size = metadata.len();
-                        let mtime = system_time_to_float(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
-                            
-                        // The Binary Gaze: Check the first few bytes
-                        let mut is_binary = false;
-                        if size > 0 {
-                                if let Ok(mut f) = File::open(path) {
-                                    let mut buffer = [0; 1024];
-                                    if let Ok(n) = f.read(&mut buffer) {
-                                        is_binary = is_binary_buffer(&buffer[..n]);
-                                    }
-                                }
-                        }
-                        results.push(FileRecord { path: path_str, size, is_binary, mtime });
-                    }
-                }
-                Err(_) => continue,
+    let (records, fresh_entries) =
+        py.allow_threads(move || wire::walk_with_cache(&root, hidden, &snapshot, use_cache));
+
+    if let Some(c) = cache {
+        c.borrow_mut(py).entries = fresh_entries;
+    }
+
+    Ok(records
+        .into_iter()
+        .map(|r| FileRecord::new(r.path, r.size, r.is_binary, r.mtime, r.sha256))
+        .collect())
+}
+
+/// Hash a file in full (mirroring `hash_file`) while also running the
+/// first-KB binary sniff, for the scan_cache cold/changed-file path.
+pub(crate) fn hash_and_sniff(path: &std::path::Path, size: u64) -> (bool, String) {
+    let mut is_binary = false;
+    let mut hasher = Sha256::new();
+
+    if size > 0 {
+        if let Ok(file) = File::open(path) {
+            if let Ok(mmap) = unsafe { MmapOptions::new().map(&file) } {
+                is_binary = is_binary_buffer(&mmap[..mmap.len().min(1024)]);
+                hasher.update(&mmap);
             }
         }
-        Ok(results)
-    })
+    }
+
+    (is_binary, hex::encode(hasher.finalize()))
 }
     
 #[pyfunction]
@@ -197,6 +227,170 @@ fn analyze_ast(py: Python, content: String, language: &str, query_str: &str) ->
     })
 }
     
+// =================================================================================
+// == THE WORKSPACE ORACLE (SYMBOL EXTRACTION)                                   ==
+// =================================================================================
+// `document_symbols`/`workspace_symbols` are new JSON-RPC methods on the
+// warm daemon: `scaffold.rs` forwards them over the existing TCP channel
+// like any other method, and the daemon answers using the PyO3 wrappers
+// below, which share this plain `extract_symbols` so the daemon never has
+// to cross into Python just to walk a capture query.
+
+#[derive(Clone, serde::Serialize)]
+pub struct SymbolInfo {
+    pub name: String,
+    pub kind: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Definitions worth surfacing as workspace symbols: functions, methods,
+/// classes/structs, and (for Rust) impl blocks.
+fn definition_query_for(language: &str) -> Option<(tree_sitter::Language, &'static str)> {
+    match language {
+        "python" => Some((
+            tree_sitter_python::language(),
+            "(function_definition name: (identifier) @name) @def
+             (class_definition name: (identifier) @name) @def",
+        )),
+        "javascript" => Some((
+            tree_sitter_javascript::language(),
+            "(function_declaration name: (identifier) @name) @def
+             (method_definition name: (property_identifier) @name) @def
+             (class_declaration name: (identifier) @name) @def",
+        )),
+        "typescript" | "tsx" => Some((
+            tree_sitter_typescript::language_tsx(),
+            "(function_declaration name: (identifier) @name) @def
+             (method_definition name: (property_identifier) @name) @def
+             (class_declaration name: (type_identifier) @name) @def",
+        )),
+        "go" => Some((
+            tree_sitter_go::language(),
+            "(function_declaration name: (identifier) @name) @def
+             (method_declaration name: (field_identifier) @name) @def
+             (type_spec name: (type_identifier) @name) @def",
+        )),
+        "rust" => Some((
+            tree_sitter_rust::language(),
+            "(function_item name: (identifier) @name) @def
+             (struct_item name: (type_identifier) @name) @def
+             (impl_item type: (type_identifier) @name) @def",
+        )),
+        _ => None,
+    }
+}
+
+/// Guess a tree-sitter language id from a file extension, for callers (like
+/// `document_symbols`) that only have a path.
+pub(crate) fn language_from_extension(path: &str) -> Option<&'static str> {
+    match std::path::Path::new(path).extension().and_then(|e| e.to_str())? {
+        "py" => Some("python"),
+        "js" | "jsx" | "mjs" => Some("javascript"),
+        "ts" => Some("typescript"),
+        "tsx" => Some("tsx"),
+        "go" => Some("go"),
+        "rs" => Some("rust"),
+        _ => None,
+    }
+}
+
+/// Walk `content`'s definitions with the per-language capture query and
+/// return them as plain, serializable symbols. Shared by the
+/// `document_symbols`/`workspace_symbols` PyO3 wrappers below.
+pub(crate) fn extract_symbols(content: &str, language: &str) -> Result<Vec<SymbolInfo>, String> {
+    let (lang, query_str) = definition_query_for(language)
+        .ok_or_else(|| format!("Unknown tongue: {}", language))?;
+
+    let mut parser = Parser::new();
+    parser.set_language(lang).map_err(|e| e.to_string())?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| "Failed to parse content".to_string())?;
+    let query = Query::new(lang, query_str).map_err(|e| format!("Invalid query: {}", e))?;
+    let mut cursor = QueryCursor::new();
+    let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+    let name_idx = query.capture_names().iter().position(|n| n == "name");
+    let def_idx = query
+        .capture_names()
+        .iter()
+        .position(|n| n == "def")
+        .ok_or_else(|| "query missing @def capture".to_string())?;
+
+    let mut symbols = Vec::new();
+    for m in matches {
+        let Some(def_capture) = m.captures.iter().find(|c| c.index as usize == def_idx) else { continue };
+        let node = def_capture.node;
+        let name = name_idx
+            .and_then(|idx| m.captures.iter().find(|c| c.index as usize == idx))
+            .map(|c| content[c.node.byte_range()].to_string())
+            .unwrap_or_else(|| "<anonymous>".to_string());
+
+        symbols.push(SymbolInfo {
+            name,
+            kind: node.kind().to_string(),
+            start_byte: node.start_byte(),
+            end_byte: node.end_byte(),
+        });
+    }
+
+    Ok(symbols)
+}
+
+#[pyclass]
+struct SymbolEntry {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    kind: String,
+    #[pyo3(get)]
+    path: String,
+    #[pyo3(get)]
+    start_byte: usize,
+    #[pyo3(get)]
+    end_byte: usize,
+}
+
+fn symbol_entries(path: &str, symbols: Vec<SymbolInfo>) -> Vec<SymbolEntry> {
+    symbols
+        .into_iter()
+        .map(|s| SymbolEntry { name: s.name, kind: s.kind, path: path.to_string(), start_byte: s.start_byte, end_byte: s.end_byte })
+        .collect()
+}
+
+#[pyfunction]
+fn document_symbols(path: String) -> PyResult<Vec<SymbolEntry>> {
+    let language = language_from_extension(&path)
+        .ok_or_else(|| PyValueError::new_err(format!("no known language for {}", path)))?;
+    let content = fs::read_to_string(&path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let symbols = extract_symbols(&content, language).map_err(PyValueError::new_err)?;
+    Ok(symbol_entries(&path, symbols))
+}
+
+#[pyfunction]
+fn workspace_symbols(py: Python, root: String, query: String) -> PyResult<Vec<SymbolEntry>> {
+    py.allow_threads(move || {
+        let query_lower = query.to_lowercase();
+        let mut entries = Vec::new();
+
+        for path in collect_source_files(&root) {
+            let Some(language) = language_from_extension(&path.to_string_lossy()) else { continue };
+            let Ok(content) = fs::read_to_string(&path) else { continue };
+            let Ok(symbols) = extract_symbols(&content, language) else { continue };
+
+            let path_str = path.to_string_lossy().replace('\\', "/");
+            entries.extend(
+                symbol_entries(&path_str, symbols)
+                    .into_iter()
+                    .filter(|s| query_lower.is_empty() || s.name.to_lowercase().contains(&query_lower)),
+            );
+        }
+
+        Ok(entries)
+    })
+}
+
 #[pymodule]
 fn scaffold_core_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<FileRecord>()?;
@@ -205,5 +399,22 @@ fn scaffold_core_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(calculate_entropy, m)?)?;
     m.add_function(wrap_pyfunction!(read_text_file, m)?)?;
     m.add_function(wrap_pyfunction!(analyze_ast, m)?)?;
+    m.add_class::<code_index::CodeChunk>()?;
+    m.add_class::<code_index::CodeIndex>()?;
+    m.add_function(wrap_pyfunction!(code_index::index_directory, m)?)?;
+    m.add_class::<cdc::Chunk>()?;
+    m.add_class::<cdc::ChunkStore>()?;
+    m.add_function(wrap_pyfunction!(cdc::chunk_file, m)?)?;
+    m.add_class::<scan_cache::ScanCache>()?;
+    m.add_class::<scan_cache::ScanDiff>()?;
+    m.add_class::<SymbolEntry>()?;
+    m.add_function(wrap_pyfunction!(document_symbols, m)?)?;
+    m.add_function(wrap_pyfunction!(workspace_symbols, m)?)?;
+    m.add_function(wrap_pyfunction!(wire::scan_directory_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(wire::analyze_ast_bytes, m)?)?;
+    m.add_function(wrap_pyfunction!(wire::decode_records, m)?)?;
+    m.add_class::<secrets::Finding>()?;
+    m.add_function(wrap_pyfunction!(secrets::scan_secrets, m)?)?;
+    m.add_function(wrap_pyfunction!(secrets::scan_secrets_directory, m)?)?;
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,103 @@
+// ---------------------
+// Path: rust/src/scan_cache.rs
+// ---------------------
+// A persistent fingerprint cache so repeated scans of an unchanged tree
+// are near-instant: scan_directory consults this to skip re-hashing files
+// whose size and mtime haven't moved, the way a build engine fingerprints
+// its inputs.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyIOError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub size: u64,
+    pub mtime: f64,
+    pub sha256: String,
+    pub is_binary: bool,
+}
+
+// =================================================================================
+// == THE GNOSTIC VESSELS (STRUCTS)                                               ==
+// =================================================================================
+
+#[pyclass]
+pub struct ScanCache {
+    // The live state, replaced by the fresh walk's entries after each
+    // scan_directory call that is given this cache.
+    pub(crate) entries: HashMap<String, CacheEntry>,
+    // A frozen copy of whatever was loaded from disk, kept around so
+    // diff() can report what changed since then.
+    baseline: HashMap<String, CacheEntry>,
+}
+
+#[pyclass]
+pub struct ScanDiff {
+    #[pyo3(get)]
+    pub added: Vec<String>,
+    #[pyo3(get)]
+    pub removed: Vec<String>,
+    #[pyo3(get)]
+    pub modified: Vec<String>,
+}
+
+#[pymethods]
+impl ScanCache {
+    #[new]
+    fn new() -> Self {
+        ScanCache { entries: HashMap::new(), baseline: HashMap::new() }
+    }
+
+    /// Load a cache previously written by `save()`. A missing file is not
+    /// an error: it just means we're starting from a cold, empty cache.
+    #[staticmethod]
+    fn load(path: String) -> PyResult<Self> {
+        let entries: HashMap<String, CacheEntry> = match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .map_err(|e| PyIOError::new_err(format!("corrupt scan cache: {}", e)))?,
+            Err(_) => HashMap::new(),
+        };
+        Ok(ScanCache { baseline: entries.clone(), entries })
+    }
+
+    fn save(&self, path: String) -> PyResult<()> {
+        let raw = serde_json::to_string(&self.entries)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        fs::write(&path, raw).map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Compare the current (post-walk) state against whatever was loaded
+    /// from disk, reporting which paths are new, gone, or changed.
+    fn diff(&self) -> ScanDiff {
+        let mut added = Vec::new();
+        let mut modified = Vec::new();
+        for (path, entry) in &self.entries {
+            match self.baseline.get(path) {
+                None => added.push(path.clone()),
+                Some(old) => {
+                    if old.size != entry.size || old.mtime != entry.mtime || old.sha256 != entry.sha256 {
+                        modified.push(path.clone());
+                    }
+                }
+            }
+        }
+        let mut removed: Vec<String> = self
+            .baseline
+            .keys()
+            .filter(|path| !self.entries.contains_key(*path))
+            .cloned()
+            .collect();
+
+        added.sort();
+        modified.sort();
+        removed.sort();
+        ScanDiff { added, removed, modified }
+    }
+}
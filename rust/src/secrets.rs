@@ -0,0 +1,220 @@
+// ---------------------
+// Path: rust/src/secrets.rs
+// ---------------------
+// Structural secret scanning: instead of grepping whole files, run the
+// existing tree-sitter queries to isolate string/comment literals, then
+// flag the ones that look like embedded key material, by Shannon entropy
+// (calculate_entropy) and by a few high-confidence regexes.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use regex::Regex;
+use std::fs;
+use std::sync::OnceLock;
+use std::thread;
+use tree_sitter::{Parser, Query, QueryCursor};
+
+use crate::{calculate_entropy, collect_source_files};
+
+const MIN_LEN: usize = 20;
+const ENTROPY_THRESHOLD: f64 = 4.5;
+
+// =================================================================================
+// == THE GNOSTIC VESSELS (STRUCTS)                                               ==
+// =================================================================================
+
+#[pyclass]
+pub struct Finding {
+    #[pyo3(get)]
+    pub path: String,
+    #[pyo3(get)]
+    pub start_byte: usize,
+    #[pyo3(get)]
+    pub end_byte: usize,
+    #[pyo3(get)]
+    pub entropy: f64,
+    #[pyo3(get)]
+    pub snippet: String,
+    #[pyo3(get)]
+    pub rule: String,
+}
+
+#[pymethods]
+impl Finding {
+    fn __repr__(&self) -> String {
+        format!("<Finding path='{}' rule='{}' entropy={:.2}>", self.path, self.rule, self.entropy)
+    }
+}
+
+// =================================================================================
+// == THE IRON ARTISANS (FUNCTIONS)                                               ==
+// =================================================================================
+
+/// Capture queries for the literal kinds worth inspecting: string-ish
+/// nodes (where a leaked key usually lives) and comments (where it
+/// sometimes gets pasted during debugging).
+fn literal_query_for(language: &str) -> Option<(tree_sitter::Language, &'static str)> {
+    match language {
+        "python" => Some((tree_sitter_python::language(), "[(string) (comment)] @literal")),
+        "javascript" => Some((
+            tree_sitter_javascript::language(),
+            "[(string) (template_string) (comment)] @literal",
+        )),
+        "typescript" | "tsx" => Some((
+            tree_sitter_typescript::language_tsx(),
+            "[(string) (template_string) (comment)] @literal",
+        )),
+        "go" => Some((
+            tree_sitter_go::language(),
+            "[(interpreted_string_literal) (raw_string_literal) (comment)] @literal",
+        )),
+        "rust" => Some((
+            tree_sitter_rust::language(),
+            "[(string_literal) (line_comment) (block_comment)] @literal",
+        )),
+        _ => None,
+    }
+}
+
+/// A regex rule that, on its own, is high-confidence enough to flag a
+/// literal regardless of entropy.
+struct RegexRule {
+    name: &'static str,
+    pattern: Regex,
+}
+
+// Compiled once and shared across every file/thread a scan touches, rather
+// than re-compiling three regexes per file over a tree of hundreds of
+// thousands of files.
+static REGEX_RULES: OnceLock<Vec<RegexRule>> = OnceLock::new();
+
+fn regex_rules() -> &'static [RegexRule] {
+    REGEX_RULES.get_or_init(|| {
+        vec![
+            RegexRule { name: "aws_access_key_id", pattern: Regex::new(r"AKIA[0-9A-Z]{16}").unwrap() },
+            RegexRule { name: "pem_private_key", pattern: Regex::new(r"-----BEGIN (RSA |EC |OPENSSH )?PRIVATE KEY-----").unwrap() },
+            RegexRule { name: "long_hex_secret", pattern: Regex::new(r"\b[0-9a-fA-F]{32,}\b").unwrap() },
+        ]
+    })
+}
+
+/// Entropy-worthy candidates are mostly base64/hex-alphabet: letters,
+/// digits, and the handful of symbols base64/URL-safe encodings use.
+fn looks_like_secret_charset(s: &str) -> bool {
+    let relevant = s.chars().filter(|c| !c.is_whitespace()).count().max(1);
+    let matching = s
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '_' | '-'))
+        .count();
+    (matching as f64 / relevant as f64) >= 0.9
+}
+
+fn scan_literal(text: &str, rules: &[RegexRule]) -> Option<(f64, String)> {
+    for rule in rules {
+        if rule.pattern.is_match(text) {
+            return Some((calculate_entropy(text.as_bytes()), rule.name.to_string()));
+        }
+    }
+
+    if text.len() >= MIN_LEN && looks_like_secret_charset(text) {
+        let entropy = calculate_entropy(text.as_bytes());
+        if entropy > ENTROPY_THRESHOLD {
+            return Some((entropy, "high_entropy_literal".to_string()));
+        }
+    }
+
+    None
+}
+
+fn snippet_of(text: &str) -> String {
+    const MAX: usize = 80;
+    if text.len() <= MAX {
+        text.to_string()
+    } else {
+        // Back off to the nearest char boundary so we don't split a
+        // multi-byte UTF-8 character in half.
+        let mut end = MAX;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}…", &text[..end])
+    }
+}
+
+#[pyfunction]
+pub fn scan_secrets(py: Python, content: String, language: &str) -> PyResult<Vec<Finding>> {
+    py.allow_threads(move || scan_content(&content, language, ""))
+}
+
+fn scan_content(content: &str, language: &str, path: &str) -> PyResult<Vec<Finding>> {
+    let (lang, query_str) = literal_query_for(language)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown tongue: {}", language)))?;
+
+    let mut parser = Parser::new();
+    parser.set_language(lang).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let tree = parser.parse(content, None).ok_or_else(|| PyValueError::new_err("Failed to parse content"))?;
+    let query = Query::new(lang, query_str).map_err(|e| PyValueError::new_err(format!("Invalid query: {}", e)))?;
+    let mut cursor = QueryCursor::new();
+    let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+    let rules = regex_rules();
+    let mut findings = Vec::new();
+
+    for m in matches {
+        for capture in m.captures {
+            let node = capture.node;
+            let range = node.byte_range();
+            let text = &content[range.clone()];
+
+            if let Some((entropy, rule)) = scan_literal(text, &rules) {
+                findings.push(Finding {
+                    path: path.to_string(),
+                    start_byte: range.start,
+                    end_byte: range.end,
+                    entropy,
+                    snippet: snippet_of(text),
+                    rule,
+                });
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+/// Scan every file under `root`, fanned out across the same auto-detected
+/// thread count `scan_directory` uses: each worker takes a slice of the
+/// file list and scans it independently, so no lock is held on the hot path.
+#[pyfunction]
+pub fn scan_secrets_directory(py: Python, root: String) -> PyResult<Vec<Finding>> {
+    py.allow_threads(move || {
+        let files = collect_source_files(&root);
+        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(files.len().max(1));
+        let chunk_size = (files.len() + threads - 1) / threads.max(1);
+
+        let findings = thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size.max(1))
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        let mut found = Vec::new();
+                        for path in chunk {
+                            let Some(language) = crate::language_from_extension(&path.to_string_lossy()) else { continue };
+                            let Ok(content) = fs::read_to_string(path) else { continue };
+                            let path_str = path.to_string_lossy().replace('\\', "/");
+
+                            if let Ok(matches) = scan_content(&content, language, &path_str) {
+                                found.extend(matches);
+                            }
+                        }
+                        found
+                    })
+                })
+                .collect();
+
+            handles.into_iter().flat_map(|h| h.join().unwrap_or_default()).collect()
+        });
+
+        Ok(findings)
+    })
+}
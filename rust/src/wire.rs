@@ -0,0 +1,259 @@
+// ---------------------
+// Path: rust/src/wire.rs
+// ---------------------
+// Binary (postcard) wire format for bulk results. Converting thousands of
+// FileRecord/HashMap objects across the PyO3 boundary means one PyObject
+// per field per row; postcard lets us pay for one allocation and one copy
+// per call instead, and lets the daemon ship the same bytes over TCP.
+
+use pyo3::prelude::*;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::time::SystemTime;
+use std::thread;
+use tree_sitter::{Parser, Query, QueryCursor};
+
+use crate::{hash_and_sniff, is_binary_buffer, system_time_to_float};
+use crate::scan_cache::{CacheEntry, ScanCache};
+use ignore::WalkBuilder;
+use std::collections::HashMap;
+
+// =================================================================================
+// == THE WIRE VESSELS (SERDE MIRRORS)                                            ==
+// =================================================================================
+
+#[derive(Serialize, Deserialize)]
+pub struct WireFileRecord {
+    pub path: String,
+    pub size: u64,
+    pub is_binary: bool,
+    pub mtime: f64,
+    // Mirrors `FileRecord::sha256`: only populated when the caller passed a
+    // `ScanCache`, same as the non-bulk `scan_directory`.
+    pub sha256: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WireCodeChunk {
+    pub path: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub kind: String,
+    pub text: String,
+    pub symbol_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct WireAstMatch {
+    pub capture: String,
+    pub kind: String,
+    pub text: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// A tagged envelope so `decode_records` can tell, from the bytes alone,
+/// which of the three record shapes it's holding.
+#[derive(Serialize, Deserialize)]
+pub enum RecordBatch {
+    Files(Vec<WireFileRecord>),
+    Chunks(Vec<WireCodeChunk>),
+    AstMatches(Vec<WireAstMatch>),
+}
+
+impl RecordBatch {
+    fn into_py(self, py: Python) -> PyResult<PyObject> {
+        match self {
+            RecordBatch::Files(records) => {
+                let list: Vec<PyObject> = records
+                    .into_iter()
+                    .map(|r| {
+                        crate::FileRecord::new(r.path, r.size, r.is_binary, r.mtime, r.sha256)
+                            .into_py(py)
+                    })
+                    .collect();
+                Ok(list.into_py(py))
+            }
+            RecordBatch::Chunks(chunks) => {
+                let list: Vec<PyObject> = chunks
+                    .into_iter()
+                    .map(|c| {
+                        crate::code_index::CodeChunk {
+                            path: c.path,
+                            start_byte: c.start_byte,
+                            end_byte: c.end_byte,
+                            kind: c.kind,
+                            text: c.text,
+                            symbol_name: c.symbol_name,
+                        }
+                        .into_py(py)
+                    })
+                    .collect();
+                Ok(list.into_py(py))
+            }
+            RecordBatch::AstMatches(matches) => {
+                let list: Vec<PyObject> = matches
+                    .into_iter()
+                    .map(|m| {
+                        let mut map = std::collections::HashMap::new();
+                        map.insert("capture".to_string(), m.capture);
+                        map.insert("type".to_string(), m.kind);
+                        map.insert("text".to_string(), m.text);
+                        map.insert("start_byte".to_string(), m.start_byte.to_string());
+                        map.insert("end_byte".to_string(), m.end_byte.to_string());
+                        map.into_py(py)
+                    })
+                    .collect();
+                Ok(list.into_py(py))
+            }
+        }
+    }
+}
+
+// =================================================================================
+// == THE IRON ARTISANS (FUNCTIONS)                                               ==
+// =================================================================================
+
+/// The walk+cache logic shared by `scan_directory` (which converts the
+/// result to `Vec<FileRecord>`) and `scan_directory_bytes` (which postcard-
+/// encodes it as-is): for each file, reuse the cached hash/binary flag on a
+/// size+mtime match, otherwise hash it fresh (like `hash_file`) and record
+/// a fresh cache entry.
+pub(crate) fn walk_with_cache(
+    root: &str,
+    hidden: bool,
+    snapshot: &HashMap<String, CacheEntry>,
+    use_cache: bool,
+) -> (Vec<WireFileRecord>, HashMap<String, CacheEntry>) {
+    let mut records = Vec::new();
+    let mut fresh_entries = HashMap::new();
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    let walker = WalkBuilder::new(root)
+        .hidden(!hidden)
+        .git_ignore(true)
+        .threads(threads)
+        .build();
+
+    for entry in walker.filter_map(|e| e.ok()) {
+        if !entry.path().is_file() { continue; }
+        let path = entry.path();
+        let path_str = path.to_string_lossy().replace('\\', "/");
+
+        let Ok(metadata) = entry.metadata() else { continue };
+        let size = metadata.len();
+        let mtime = system_time_to_float(metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+
+        let cached = snapshot.get(&path_str).filter(|e| e.size == size && e.mtime == mtime);
+
+        let (is_binary, sha256) = if let Some(entry) = cached {
+            (entry.is_binary, Some(entry.sha256.clone()))
+        } else if use_cache {
+            let (is_binary, sha256) = hash_and_sniff(path, size);
+            (is_binary, Some(sha256))
+        } else {
+            let mut is_binary = false;
+            if size > 0 {
+                if let Ok(mut f) = File::open(path) {
+                    let mut buffer = [0; 1024];
+                    if let Ok(n) = f.read(&mut buffer) {
+                        is_binary = is_binary_buffer(&buffer[..n]);
+                    }
+                }
+            }
+            (is_binary, None)
+        };
+
+        if use_cache {
+            if let Some(sha256) = &sha256 {
+                fresh_entries.insert(
+                    path_str.clone(),
+                    CacheEntry { size, mtime, sha256: sha256.clone(), is_binary },
+                );
+            }
+        }
+
+        records.push(WireFileRecord { path: path_str, size, is_binary, mtime, sha256 });
+    }
+
+    (records, fresh_entries)
+}
+
+/// Bulk/bytes twin of `scan_directory`: same `cache` semantics, just
+/// encoded as one postcard blob instead of a `Vec<FileRecord>`.
+#[pyfunction]
+#[pyo3(signature = (root, hidden=false, cache=None))]
+pub fn scan_directory_bytes(
+    py: Python,
+    root: String,
+    hidden: bool,
+    cache: Option<Py<ScanCache>>,
+) -> PyResult<Vec<u8>> {
+    let snapshot = match &cache {
+        Some(c) => c.borrow(py).entries.clone(),
+        None => HashMap::new(),
+    };
+    let use_cache = cache.is_some();
+
+    let (records, fresh_entries) =
+        py.allow_threads(move || walk_with_cache(&root, hidden, &snapshot, use_cache));
+
+    if let Some(c) = cache {
+        c.borrow_mut(py).entries = fresh_entries;
+    }
+
+    postcard::to_allocvec(&RecordBatch::Files(records))
+        .map_err(|e| PyIOError::new_err(format!("postcard encode failed: {}", e)))
+}
+
+#[pyfunction]
+pub fn analyze_ast_bytes(py: Python, content: String, language: &str, query_str: &str) -> PyResult<Vec<u8>> {
+    py.allow_threads(move || {
+        let mut parser = Parser::new();
+
+        let lang = match language {
+            "python" => tree_sitter_python::language(),
+            "javascript" => tree_sitter_javascript::language(),
+            "typescript" | "tsx" => tree_sitter_typescript::language_tsx(),
+            "go" => tree_sitter_go::language(),
+            "rust" => tree_sitter_rust::language(),
+            _ => return Err(PyValueError::new_err(format!("Unknown tongue: {}", language))),
+        };
+
+        parser.set_language(lang).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let tree = parser.parse(&content, None).ok_or_else(|| PyValueError::new_err("Failed to parse content"))?;
+        let query = Query::new(lang, query_str).map_err(|e| PyValueError::new_err(format!("Invalid query: {}", e)))?;
+        let mut cursor = QueryCursor::new();
+        let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+
+        let mut records = Vec::new();
+        for m in matches {
+            for capture in m.captures {
+                let node = capture.node;
+                let range = node.byte_range();
+                records.push(WireAstMatch {
+                    capture: query.capture_names()[capture.index as usize].clone(),
+                    kind: node.kind().to_string(),
+                    text: content[range].to_string(),
+                    start_byte: node.start_byte(),
+                    end_byte: node.end_byte(),
+                });
+            }
+        }
+
+        postcard::to_allocvec(&RecordBatch::AstMatches(records))
+            .map_err(|e| PyIOError::new_err(format!("postcard encode failed: {}", e)))
+    })
+}
+
+/// Decode a `RecordBatch` in one call (the Files and AstMatches batches
+/// produced above, or a Chunks batch), handing back the native Python list
+/// (of `FileRecord`/`CodeChunk`/dicts) its shape implies.
+#[pyfunction]
+pub fn decode_records(py: Python, data: Vec<u8>) -> PyResult<PyObject> {
+    let batch: RecordBatch = postcard::from_bytes(&data)
+        .map_err(|e| PyIOError::new_err(format!("postcard decode failed: {}", e)))?;
+    batch.into_py(py)
+}